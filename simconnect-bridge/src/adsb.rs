@@ -0,0 +1,572 @@
+use super::BridgeState;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+/// Default dump1090 / Beast-format feed endpoint.
+const FEED_ADDR: &str = "127.0.0.1:30005";
+/// An even/odd CPR pair older than this can no longer be combined.
+const CPR_PAIR_MAX_AGE: Duration = Duration::from_secs(10);
+/// Aircraft with no update in this long are dropped from the traffic list.
+const ENTRY_TIMEOUT: Duration = Duration::from_secs(300);
+/// Number of decoded fixes kept per aircraft for outlier rejection.
+const POSITION_HISTORY_LEN: usize = 5;
+/// Floor of the outlier threshold: a fix is dropped when its implied speed
+/// exceeds both this and three times the buffer's median speed.
+const MAX_PLAUSIBLE_SPEED_KT: f64 = 700.0;
+/// Consecutive fixes rejected as outliers before the anchor is assumed stale
+/// and the history is reset, so a single garbled first fix can't freeze an
+/// aircraft's position for the rest of `ENTRY_TIMEOUT`.
+const MAX_CONSECUTIVE_REJECTIONS: u32 = 3;
+
+pub type IcaoAddress = u32;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TrafficAircraft {
+  pub icao: String,
+  pub callsign: Option<String>,
+  pub altitude_ft: Option<f64>,
+  pub heading_deg: Option<f64>,
+  pub gs_kt: Option<f64>,
+  pub vs_fpm: Option<f64>,
+  /// Last-fix position straight off the wire.
+  pub lat: Option<f64>,
+  pub lon: Option<f64>,
+  /// Dead-reckoned position as of the last frame processed from the feed, for
+  /// smooth rendering between fixes; it is not recomputed at broadcast time.
+  pub lat_projected: Option<f64>,
+  pub lon_projected: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CprFrame {
+  lat_cpr: u32,
+  lon_cpr: u32,
+  received_at: Instant,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Fix {
+  lat: f64,
+  lon: f64,
+  at: Instant,
+}
+
+#[derive(Default)]
+struct Entry {
+  aircraft: TrafficAircraft,
+  even: Option<CprFrame>,
+  odd: Option<CprFrame>,
+  last_seen: Option<Instant>,
+  last_fix: Option<Fix>,
+  position_history: VecDeque<Fix>,
+  consecutive_rejections: u32,
+}
+
+pub fn spawn(state: Arc<Mutex<BridgeState>>) {
+  thread::spawn(move || loop {
+    if let Err(err) = run_adsb_loop(&state) {
+      eprintln!("ADS-B error: {err}");
+      thread::sleep(RETRY_DELAY);
+    }
+  });
+}
+
+fn run_adsb_loop(state: &Arc<Mutex<BridgeState>>) -> anyhow::Result<()> {
+  let stream = TcpStream::connect(FEED_ADDR)?;
+  let mut reader = BeastReader::new(stream);
+  let mut entries: HashMap<IcaoAddress, Entry> = HashMap::new();
+
+  loop {
+    let payload = reader.next_mode_s_frame()?;
+    if let Some((icao, msg)) = decode_extended_squitter(&payload) {
+      let entry = entries.entry(icao).or_default();
+      entry.aircraft.icao = format!("{icao:06X}");
+      entry.last_seen = Some(Instant::now());
+      apply_message(entry, msg);
+    }
+
+    entries.retain(|_, entry| {
+      entry
+        .last_seen
+        .map(|t| t.elapsed() < ENTRY_TIMEOUT)
+        .unwrap_or(false)
+    });
+
+    publish(state, &entries);
+  }
+}
+
+/// Called after every processed frame, which is the only place projected
+/// positions are recomputed: the reader thread blocks on socket I/O, so
+/// while the feed is quiet the published projections do not advance.
+fn publish(state: &Arc<Mutex<BridgeState>>, entries: &HashMap<IcaoAddress, Entry>) {
+  let traffic: Vec<TrafficAircraft> = entries
+    .values()
+    .map(|e| {
+      let mut aircraft = e.aircraft.clone();
+      if let (Some(fix), Some(heading_deg), Some(gs_kt)) = (e.last_fix, aircraft.heading_deg, aircraft.gs_kt) {
+        let (lat, lon) = dead_reckon(fix.lat, fix.lon, heading_deg, gs_kt, fix.at.elapsed());
+        aircraft.lat_projected = Some(lat);
+        aircraft.lon_projected = Some(lon);
+      } else {
+        aircraft.lat_projected = aircraft.lat;
+        aircraft.lon_projected = aircraft.lon;
+      }
+      aircraft
+    })
+    .collect();
+  if let Ok(mut guard) = state.lock() {
+    guard.traffic = traffic;
+  }
+}
+
+enum AdsbMessage {
+  Identification { callsign: String },
+  AirbornePosition { odd: bool, altitude_ft: f64, lat_cpr: u32, lon_cpr: u32 },
+  AirborneVelocity { heading_deg: Option<f64>, gs_kt: Option<f64>, vs_fpm: Option<f64> },
+}
+
+fn apply_message(entry: &mut Entry, msg: AdsbMessage) {
+  match msg {
+    AdsbMessage::Identification { callsign } => {
+      entry.aircraft.callsign = Some(callsign);
+    }
+    AdsbMessage::AirborneVelocity { heading_deg, gs_kt, vs_fpm } => {
+      if let Some(heading_deg) = heading_deg {
+        entry.aircraft.heading_deg = Some(heading_deg);
+      }
+      if let Some(gs_kt) = gs_kt {
+        entry.aircraft.gs_kt = Some(gs_kt);
+      }
+      if let Some(vs_fpm) = vs_fpm {
+        entry.aircraft.vs_fpm = Some(vs_fpm);
+      }
+    }
+    AdsbMessage::AirbornePosition { odd, altitude_ft, lat_cpr, lon_cpr } => {
+      entry.aircraft.altitude_ft = Some(altitude_ft);
+      let frame = CprFrame { lat_cpr, lon_cpr, received_at: Instant::now() };
+      if odd {
+        entry.odd = Some(frame);
+      } else {
+        entry.even = Some(frame);
+      }
+
+      if let (Some(even), Some(odd)) = (entry.even, entry.odd) {
+        let newer_is_even = even.received_at >= odd.received_at;
+        let pair_age = if newer_is_even {
+          even.received_at.duration_since(odd.received_at)
+        } else {
+          odd.received_at.duration_since(even.received_at)
+        };
+        if pair_age <= CPR_PAIR_MAX_AGE {
+          if let Some((lat, lon)) = cpr_global_decode(even, odd, newer_is_even) {
+            if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+              let fix = Fix { lat, lon, at: Instant::now() };
+              if is_outlier_fix(&entry.position_history, fix) {
+                entry.consecutive_rejections += 1;
+                if entry.consecutive_rejections >= MAX_CONSECUTIVE_REJECTIONS {
+                  // The anchor this fix was compared against is probably
+                  // stale or itself garbled; drop it so the next fix is
+                  // trusted unconditionally instead of rejected forever.
+                  entry.position_history.clear();
+                  entry.consecutive_rejections = 0;
+                }
+              } else {
+                entry.consecutive_rejections = 0;
+                entry.aircraft.lat = Some(lat);
+                entry.aircraft.lon = Some(lon);
+                entry.last_fix = Some(fix);
+                entry.position_history.push_back(fix);
+                if entry.position_history.len() > POSITION_HISTORY_LEN {
+                  entry.position_history.pop_front();
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Great-circle distance between two fixes, in nautical miles.
+fn great_circle_distance_nm(a: Fix, b: Fix) -> f64 {
+  const EARTH_RADIUS_NM: f64 = 3_440.065;
+  let lat1 = a.lat.to_radians();
+  let lat2 = b.lat.to_radians();
+  let d_lat = (b.lat - a.lat).to_radians();
+  let d_lon = (b.lon - a.lon).to_radians();
+  let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+  EARTH_RADIUS_NM * 2.0 * h.sqrt().asin()
+}
+
+/// Rejects a new fix whose implied ground speed relative to the most recent
+/// history entry is wildly out of line with the median speed already
+/// observed in the jitter buffer — almost always a garbled CPR decode.
+fn is_outlier_fix(history: &VecDeque<Fix>, candidate: Fix) -> bool {
+  let Some(&last) = history.back() else {
+    return false;
+  };
+  let elapsed_hr = candidate.at.saturating_duration_since(last.at).as_secs_f64() / 3_600.0;
+  if elapsed_hr <= 0.0 {
+    return false;
+  }
+  let implied_speed_kt = great_circle_distance_nm(last, candidate) / elapsed_hr;
+
+  if history.len() < 2 {
+    return implied_speed_kt > MAX_PLAUSIBLE_SPEED_KT;
+  }
+
+  let mut speeds: Vec<f64> = history
+    .iter()
+    .zip(history.iter().skip(1))
+    .map(|(a, b)| {
+      let dt_hr = b.at.saturating_duration_since(a.at).as_secs_f64() / 3_600.0;
+      if dt_hr <= 0.0 {
+        0.0
+      } else {
+        great_circle_distance_nm(*a, *b) / dt_hr
+      }
+    })
+    .collect();
+  speeds.sort_by(|a, b| a.total_cmp(b));
+  let median_speed_kt = speeds[speeds.len() / 2];
+
+  implied_speed_kt > (median_speed_kt * 3.0).max(MAX_PLAUSIBLE_SPEED_KT)
+}
+
+/// Dead-reckons a position forward along a great-circle bearing.
+fn dead_reckon(lat: f64, lon: f64, heading_deg: f64, gs_kt: f64, elapsed: Duration) -> (f64, f64) {
+  const EARTH_RADIUS_NM: f64 = 3_440.065;
+  let distance_nm = gs_kt * elapsed.as_secs_f64() / 3_600.0;
+  let angular_distance = distance_nm / EARTH_RADIUS_NM;
+  let bearing = heading_deg.to_radians();
+  let lat1 = lat.to_radians();
+  let lon1 = lon.to_radians();
+
+  let lat2 = (lat1.sin() * angular_distance.cos() + lat1.cos() * angular_distance.sin() * bearing.cos()).asin();
+  let lon2 = lon1
+    + (bearing.sin() * angular_distance.sin() * lat1.cos())
+      .atan2(angular_distance.cos() - lat1.sin() * lat2.sin());
+
+  (lat2.to_degrees(), lon2.to_degrees())
+}
+
+/// Decodes a 14-byte Extended Squitter (DF17) payload into an ADS-B message,
+/// returning the transmitting aircraft's ICAO address alongside it.
+fn decode_extended_squitter(payload: &[u8]) -> Option<(IcaoAddress, AdsbMessage)> {
+  if payload.len() < 11 {
+    return None;
+  }
+  let df = payload[0] >> 3;
+  if df != 17 && df != 18 {
+    return None;
+  }
+  let icao = u32::from_be_bytes([0, payload[1], payload[2], payload[3]]);
+  let me = &payload[4..11];
+  let tc = me[0] >> 3;
+
+  let msg = match tc {
+    1..=4 => AdsbMessage::Identification { callsign: decode_callsign(me) },
+    9..=18 | 20..=22 => {
+      let odd = (me[2] >> 2) & 1 == 1;
+      let altitude_ft = decode_altitude(me)?;
+      let lat_cpr = (u32::from(me[2] & 0x03) << 15) | (u32::from(me[3]) << 7) | (u32::from(me[4]) >> 1);
+      let lon_cpr = (u32::from(me[4] & 0x01) << 16) | (u32::from(me[5]) << 8) | u32::from(me[6]);
+      AdsbMessage::AirbornePosition { odd, altitude_ft, lat_cpr, lon_cpr }
+    }
+    19 => decode_velocity(me)?,
+    _ => return None,
+  };
+
+  Some((icao, msg))
+}
+
+const CALLSIGN_CHARSET: &[u8] = b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ#####_###############0123456789######";
+
+fn decode_callsign(me: &[u8]) -> String {
+  let bits = &me[1..7];
+  let mut chars = Vec::with_capacity(8);
+  let mut bit_offset = 0usize;
+  for _ in 0..8 {
+    let byte_index = bit_offset / 8;
+    let shift = bit_offset % 8;
+    let hi = bits.get(byte_index).copied().unwrap_or(0);
+    let lo = bits.get(byte_index + 1).copied().unwrap_or(0);
+    let combined = (u16::from(hi) << 8) | u16::from(lo);
+    let code = ((combined >> (16 - shift - 6)) & 0x3f) as usize;
+    chars.push(CALLSIGN_CHARSET.get(code).copied().unwrap_or(b'#'));
+    bit_offset += 6;
+  }
+  String::from_utf8_lossy(&chars).trim_end_matches('#').to_string()
+}
+
+fn decode_altitude(me: &[u8]) -> Option<f64> {
+  let raw = (u16::from(me[1]) << 4) | (u16::from(me[2]) >> 4);
+  if raw == 0 {
+    return None;
+  }
+  let q_bit = (raw >> 4) & 1;
+  if q_bit == 1 {
+    let n = ((raw >> 5) << 4) | (raw & 0xf);
+    Some(f64::from(n) * 25.0 - 1000.0)
+  } else {
+    None
+  }
+}
+
+fn decode_velocity(me: &[u8]) -> Option<AdsbMessage> {
+  let subtype = me[0] & 0x07;
+  if subtype != 1 && subtype != 2 {
+    return None;
+  }
+
+  // A raw subfield of 0 is the spec's "no data for this component" sentinel,
+  // and must be checked before the 1-biased subtraction below, or absent
+  // data reads back as the maximum representable speed instead of missing.
+  let raw_ew = (u16::from(me[1] & 0x03) << 8) | u16::from(me[2]);
+  let raw_ns = (u16::from(me[3] & 0x7f) << 3) | u16::from(me[4] >> 5);
+  let (heading_deg, gs_kt) = if raw_ew == 0 || raw_ns == 0 {
+    (None, None)
+  } else {
+    let vew_sign = if (me[1] >> 2) & 1 == 1 { -1.0 } else { 1.0 };
+    let vns_sign = if (me[3] >> 7) & 1 == 1 { -1.0 } else { 1.0 };
+    let ew_kt = vew_sign * f64::from(raw_ew - 1);
+    let ns_kt = vns_sign * f64::from(raw_ns - 1);
+    let gs_kt = (ew_kt * ew_kt + ns_kt * ns_kt).sqrt();
+    let heading_deg = ew_kt.atan2(ns_kt).to_degrees().rem_euclid(360.0);
+    (Some(heading_deg), Some(gs_kt))
+  };
+
+  let raw_vs = (u16::from(me[5] & 0x07) << 6) | u16::from(me[6] >> 2);
+  let vs_fpm = if raw_vs == 0 {
+    None
+  } else {
+    let vs_sign = if (me[5] >> 3) & 1 == 1 { -1.0 } else { 1.0 };
+    Some(vs_sign * f64::from(raw_vs - 1) * 64.0)
+  };
+
+  if heading_deg.is_none() && gs_kt.is_none() && vs_fpm.is_none() {
+    return None;
+  }
+
+  Some(AdsbMessage::AirborneVelocity { heading_deg, gs_kt, vs_fpm })
+}
+
+/// CPR global position decode (even + odd frame pair) per the ADS-B spec.
+/// `newer_is_even` selects which of the pair is used as the reference
+/// latitude/longitude zone, per the standard recommendation of decoding
+/// relative to the most recently received frame.
+fn cpr_global_decode(even: CprFrame, odd: CprFrame, newer_is_even: bool) -> Option<(f64, f64)> {
+  const NZ: f64 = 15.0;
+  const CPR_SCALE: f64 = 131_072.0; // 2^17
+
+  let lat_cpr_even = f64::from(even.lat_cpr) / CPR_SCALE;
+  let lat_cpr_odd = f64::from(odd.lat_cpr) / CPR_SCALE;
+
+  let d_lat_even = 360.0 / (4.0 * NZ);
+  let d_lat_odd = 360.0 / (4.0 * NZ - 1.0);
+
+  let j = (59.0 * lat_cpr_even - 60.0 * lat_cpr_odd + 0.5).floor();
+
+  let mut lat_even = d_lat_even * (j.rem_euclid(60.0) + lat_cpr_even);
+  let mut lat_odd = d_lat_odd * (j.rem_euclid(59.0) + lat_cpr_odd);
+  if lat_even >= 270.0 {
+    lat_even -= 360.0;
+  }
+  if lat_odd >= 270.0 {
+    lat_odd -= 360.0;
+  }
+
+  if cpr_nl(lat_even) != cpr_nl(lat_odd) {
+    // The pair straddles a latitude-zone boundary; wait for the next pair.
+    return None;
+  }
+
+  let lat = if newer_is_even { lat_even } else { lat_odd };
+  let nl = cpr_nl(lat);
+
+  let lon_cpr_even = f64::from(even.lon_cpr) / CPR_SCALE;
+  let lon_cpr_odd = f64::from(odd.lon_cpr) / CPR_SCALE;
+  let ni = if newer_is_even { nl.max(1.0) } else { (nl - 1.0).max(1.0) };
+  let m = (lon_cpr_even * (nl - 1.0) - lon_cpr_odd * nl + 0.5).floor();
+  let d_lon = 360.0 / ni;
+  let lon_cpr = if newer_is_even { lon_cpr_even } else { lon_cpr_odd };
+  let mut lon = d_lon * (m.rem_euclid(ni) + lon_cpr);
+  if lon >= 180.0 {
+    lon -= 360.0;
+  }
+
+  Some((lat, lon))
+}
+
+/// Number of geographic longitude zones at a given latitude (NL function).
+fn cpr_nl(lat: f64) -> f64 {
+  const NZ: f64 = 15.0;
+  if lat.abs() >= 87.0 {
+    return 1.0;
+  }
+  let lat_rad = lat.to_radians();
+  let a = 1.0 - (std::f64::consts::PI / (2.0 * NZ)).cos();
+  let b = lat_rad.cos().powi(2);
+  (2.0 * std::f64::consts::PI / (1.0 - a / b).acos())
+    .floor()
+    .max(1.0)
+}
+
+/// Minimal reader for the Beast binary AVR feed format used by dump1090.
+struct BeastReader {
+  stream: TcpStream,
+  buf: Vec<u8>,
+}
+
+impl BeastReader {
+  fn new(stream: TcpStream) -> Self {
+    Self { stream, buf: Vec::new() }
+  }
+
+  /// Reads frames until a Mode S long (type `0x33`) frame is found, of any
+  /// downlink format, returning its full 14-byte payload (header, ME and
+  /// parity) with Beast escaping undone.
+  fn next_mode_s_frame(&mut self) -> anyhow::Result<Vec<u8>> {
+    loop {
+      let frame_type = self.read_unescaped_byte()?;
+      // timestamp (6 bytes) + signal level (1 byte) are not needed here.
+      for _ in 0..7 {
+        self.read_unescaped_byte()?;
+      }
+      let payload_len = match frame_type {
+        0x32 => 7,  // Mode S short
+        0x33 => 14, // Mode S long
+        0x31 => 2,  // Mode A/C
+        _ => 0,
+      };
+      let mut payload = Vec::with_capacity(payload_len);
+      for _ in 0..payload_len {
+        payload.push(self.read_unescaped_byte()?);
+      }
+      if frame_type == 0x33 {
+        return Ok(payload);
+      }
+    }
+  }
+
+  fn read_unescaped_byte(&mut self) -> anyhow::Result<u8> {
+    let byte = self.read_raw_byte()?;
+    if byte == 0x1a {
+      // 0x1a always introduces either an escaped literal 0x1a (doubled,
+      // 0x1a 0x1a) or an unescaped byte that follows it unmodified — a
+      // frame's type byte included, since the type byte is never escaped.
+      // Either way the byte to hand back is whatever comes next.
+      return self.read_raw_byte();
+    }
+    Ok(byte)
+  }
+
+  fn read_raw_byte(&mut self) -> anyhow::Result<u8> {
+    if self.buf.is_empty() {
+      let mut chunk = [0u8; 4096];
+      let n = self.stream.read(&mut chunk)?;
+      if n == 0 {
+        anyhow::bail!("ADS-B feed closed the connection");
+      }
+      self.buf.extend_from_slice(&chunk[..n]);
+      self.buf.reverse();
+    }
+    self.buf.pop().ok_or_else(|| anyhow::anyhow!("empty read buffer"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+  use std::net::TcpListener;
+
+  #[test]
+  fn cpr_global_decode_matches_known_worked_example() {
+    // Classic even/odd CPR pair worked example (Junzi Sun, "The 1090MHz
+    // Riddle"): decodes to Schiphol-area coordinates ~52.2572N, 3.9194E.
+    let even = CprFrame { lat_cpr: 93_000, lon_cpr: 51_372, received_at: Instant::now() };
+    let odd = CprFrame { lat_cpr: 74_158, lon_cpr: 50_194, received_at: Instant::now() };
+
+    let (lat, lon) = cpr_global_decode(even, odd, true).expect("straddle-free pair should decode");
+
+    assert!((lat - 52.257_202_148_437_5).abs() < 1e-9);
+    assert!((lon - 3.919_372_558_593_75).abs() < 1e-9);
+  }
+
+  fn airborne_position_payload(odd_bit_set: bool) -> Vec<u8> {
+    let mut me = [0u8; 7];
+    me[0] = 11 << 3; // TC 11: airborne position, SS/NICsb bits left at 0.
+    me[1] = 0x01; // non-zero, Q-bit-set altitude so decode_altitude succeeds.
+    if odd_bit_set {
+      me[2] |= 0b0000_0100; // F bit: ME bit 21, i.e. bit 2 of me[2].
+    }
+    let mut payload = vec![17 << 3, 0xAB, 0xCD, 0xEF];
+    payload.extend_from_slice(&me);
+    payload
+  }
+
+  #[test]
+  fn decode_extended_squitter_reads_parity_bit_from_me2_not_me0() {
+    let (icao, msg) = decode_extended_squitter(&airborne_position_payload(true)).expect("should decode");
+    assert_eq!(icao, 0x00AB_CDEF);
+    match msg {
+      AdsbMessage::AirbornePosition { odd, .. } => assert!(odd, "F bit set in me[2] should decode as an odd frame"),
+      _ => panic!("expected AirbornePosition"),
+    }
+
+    let (_, msg) = decode_extended_squitter(&airborne_position_payload(false)).expect("should decode");
+    match msg {
+      AdsbMessage::AirbornePosition { odd, .. } => assert!(!odd, "F bit clear in me[2] should decode as an even frame"),
+      _ => panic!("expected AirbornePosition"),
+    }
+  }
+
+  fn escape(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for &b in bytes {
+      out.push(b);
+      if b == 0x1a {
+        out.push(0x1a);
+      }
+    }
+    out
+  }
+
+  #[test]
+  fn beast_reader_unescapes_doubled_0x1a_and_parses_a_long_frame() {
+    // Timestamp and payload each include a literal 0x1a byte, so this also
+    // exercises the doubled-0x1a escape path, not just the leading sync.
+    let timestamp = [0x00, 0x01, 0x1a, 0x02, 0x03, 0x04];
+    let signal = [0x55];
+    let payload: Vec<u8> = vec![0x8D, 0x48, 0x40, 0xD6, 0x1a, 0x20, 0x2C, 0xC3, 0x71, 0xC3, 0x2C, 0xE0, 0x57, 0x98];
+    assert_eq!(payload.len(), 14);
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind loopback listener");
+    let addr = listener.local_addr().expect("local addr");
+    let payload_for_writer = payload.clone();
+    let writer = thread::spawn(move || {
+      let (mut stream, _) = listener.accept().expect("accept connection");
+      let mut wire = vec![0x1a, 0x33];
+      wire.extend(escape(&timestamp));
+      wire.extend(escape(&signal));
+      wire.extend(escape(&payload_for_writer));
+      stream.write_all(&wire).expect("write frame");
+    });
+
+    let stream = TcpStream::connect(addr).expect("connect to loopback listener");
+    let mut reader = BeastReader::new(stream);
+    let parsed = reader.next_mode_s_frame().expect("should parse a Mode S long frame");
+
+    writer.join().expect("writer thread should not panic");
+    assert_eq!(parsed, payload);
+  }
+}
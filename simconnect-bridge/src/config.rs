@@ -0,0 +1,95 @@
+use serde::Deserialize;
+use std::path::Path;
+
+/// Env var pointing at the config file; falls back to `DEFAULT_CONFIG_PATH`.
+const CONFIG_PATH_ENV: &str = "BRIDGE_CONFIG";
+const DEFAULT_CONFIG_PATH: &str = "bridge.toml";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+  pub bind_addr: String,
+  pub snapshot_rate_ms: u64,
+  pub simvars: Vec<SimVarDef>,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      bind_addr: "127.0.0.1:8383".to_string(),
+      snapshot_rate_ms: 200,
+      simvars: default_simvars(),
+    }
+  }
+}
+
+/// One SimConnect variable to subscribe to, and the `BridgeSnapshot` field it
+/// feeds. A `field` not already named on `BridgeSnapshot` lands in its
+/// `extra` map instead, so aircraft-specific gauges don't need a code change.
+/// The helper names `on_ground`, `eng1_on`..`eng4_on`, `fuel_total_lb` and
+/// `weight_lb` feed the reader's own phase and fuel logic and never reach
+/// the snapshot.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimVarDef {
+  pub name: String,
+  pub unit: String,
+  pub field: String,
+}
+
+fn default_simvars() -> Vec<SimVarDef> {
+  [
+    ("PLANE ALTITUDE", "Feet", "altitude_ft"),
+    ("AIRSPEED INDICATED", "Knots", "ias_kt"),
+    ("GROUND VELOCITY", "Knots", "gs_kt"),
+    ("AIRSPEED MACH", "Mach", "mach"),
+    ("VERTICAL SPEED", "Feet per minute", "vs_fpm"),
+    ("SIM ON GROUND", "Bool", "on_ground"),
+    ("GENERAL ENG COMBUSTION:1", "Bool", "eng1_on"),
+    ("GENERAL ENG COMBUSTION:2", "Bool", "eng2_on"),
+    ("GENERAL ENG COMBUSTION:3", "Bool", "eng3_on"),
+    ("GENERAL ENG COMBUSTION:4", "Bool", "eng4_on"),
+    ("FUEL TOTAL QUANTITY WEIGHT", "Pounds", "fuel_total_lb"),
+    ("TOTAL WEIGHT", "Pounds", "weight_lb"),
+    ("GPS FLIGHT PLAN TOTAL DISTANCE", "Nautical miles", "flightplan_total_nm"),
+    ("GPS FLIGHT PLAN DISTANCE", "Nautical miles", "flightplan_remaining_nm"),
+  ]
+  .into_iter()
+  .map(|(name, unit, field)| SimVarDef {
+    name: name.to_string(),
+    unit: unit.to_string(),
+    field: field.to_string(),
+  })
+  .collect()
+}
+
+impl Config {
+  /// Loads the file named by `BRIDGE_CONFIG` (or `bridge.toml` in the
+  /// current directory if unset) if it exists, falling back to defaults
+  /// otherwise, then applies env overrides for the bind address and
+  /// snapshot rate. The file is parsed as JSON if its extension is
+  /// `.json`, TOML otherwise.
+  pub fn load() -> anyhow::Result<Self> {
+    let path = std::env::var(CONFIG_PATH_ENV).unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+    let mut config = Self::from_file(Path::new(&path))?;
+
+    if let Ok(bind_addr) = std::env::var("BRIDGE_BIND_ADDR") {
+      config.bind_addr = bind_addr;
+    }
+    if let Ok(rate) = std::env::var("BRIDGE_SNAPSHOT_RATE_MS") {
+      config.snapshot_rate_ms = rate.parse()?;
+    }
+
+    Ok(config)
+  }
+
+  fn from_file(path: &Path) -> anyhow::Result<Self> {
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+    let text = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+      Some("json") => Ok(serde_json::from_str(&text)?),
+      _ => Ok(toml::from_str(&text)?),
+    }
+  }
+}
@@ -1,16 +1,23 @@
 use futures_util::{SinkExt, StreamExt};
-use serde::Serialize;
+use protocol::{BridgeEvent, Subscription};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use tokio::net::TcpListener;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use tokio::time::{interval, Duration};
 use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message;
 
+mod adsb;
+mod config;
+mod protocol;
+mod recorder;
 #[cfg(windows)]
 mod simconnect_reader;
 
-#[derive(Clone, Debug, Serialize, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
 struct BridgeSnapshot {
   time: u64,
   altitude_ft: Option<f64>,
@@ -32,35 +39,100 @@ struct BridgeSnapshot {
   fuel_total_kg: Option<f64>,
   fuel_burn_kg: Option<f64>,
   weight_kg: Option<f64>,
+  /// Values from config-declared SimConnect variables with no dedicated
+  /// field above (e.g. Concorde's visor/nose position, afterburner state).
+  #[serde(flatten)]
+  extra: HashMap<String, f64>,
 }
 
-#[derive(Clone, Debug, Serialize)]
-struct BridgeMessage<'a> {
-  r#type: &'a str,
-  payload: &'a BridgeSnapshot,
+impl BridgeSnapshot {
+  /// Routes a named SimConnect value onto its dedicated `f64` field, or into
+  /// `extra` for any other name, including the names of the snapshot's
+  /// non-`f64` fields (`time`, `phase`, `dep_icao`, ...).
+  fn set_field(&mut self, field: &str, value: f64) {
+    match field {
+      "altitude_ft" => self.altitude_ft = Some(value),
+      "ias_kt" => self.ias_kt = Some(value),
+      "gs_kt" => self.gs_kt = Some(value),
+      "mach" => self.mach = Some(value),
+      "vs_fpm" => self.vs_fpm = Some(value),
+      "heading_deg" => self.heading_deg = Some(value),
+      "lat" => self.lat = Some(value),
+      "lon" => self.lon = Some(value),
+      "flightplan_total_nm" => self.flightplan_total_nm = Some(value),
+      "flightplan_remaining_nm" => self.flightplan_remaining_nm = Some(value),
+      "touchdown_fpm" => self.touchdown_fpm = Some(value),
+      "fuel_total_kg" => self.fuel_total_kg = Some(value),
+      "fuel_burn_kg" => self.fuel_burn_kg = Some(value),
+      "weight_kg" => self.weight_kg = Some(value),
+      other => {
+        self.extra.insert(other.to_string(), value);
+      }
+    }
+  }
 }
 
 #[derive(Default)]
 struct BridgeState {
   snapshot: BridgeSnapshot,
+  traffic: Vec<adsb::TrafficAircraft>,
+}
+
+/// `--replay <file>` feeds a prior recording into the bridge instead of
+/// SimConnect; `--speed <multiplier>` and `--loop` tune playback; `--record
+/// <file>` appends every broadcast snapshot to disk for later replay.
+struct Cli {
+  replay: Option<String>,
+  speed: f64,
+  loop_replay: bool,
+  record: Option<String>,
+}
+
+fn parse_cli() -> Cli {
+  let mut cli = Cli { replay: None, speed: 1.0, loop_replay: false, record: None };
+  let mut args = std::env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--replay" => cli.replay = args.next(),
+      "--speed" => cli.speed = args.next().and_then(|v| v.parse().ok()).unwrap_or(1.0),
+      "--loop" => cli.loop_replay = true,
+      "--record" => cli.record = args.next(),
+      other => eprintln!("Unknown argument: {other}"),
+    }
+  }
+  cli
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-  let addr: SocketAddr = "127.0.0.1:8383".parse().expect("invalid bind address");
+  let cli = parse_cli();
+  let config = config::Config::load()?;
+  let addr: SocketAddr = config.bind_addr.parse().expect("invalid bind address");
   let listener = TcpListener::bind(addr).await?;
   println!("SimConnect bridge listening on ws://{addr}");
 
   let state = Arc::new(Mutex::new(BridgeState::default()));
-  let (tx, _rx) = broadcast::channel::<String>(128);
+  let (tx, _rx) = broadcast::channel::<BridgeEvent>(128);
+
+  spawn_snapshot_broadcaster(state.clone(), tx.clone(), Duration::from_millis(config.snapshot_rate_ms));
 
-  spawn_snapshot_broadcaster(state.clone(), tx.clone());
-  spawn_simconnect_reader(state.clone());
+  if let Some(record_path) = cli.record {
+    recorder::spawn_writer(record_path, tx.subscribe());
+  }
+
+  if let Some(replay_path) = cli.replay {
+    recorder::spawn_replay(state.clone(), replay_path, cli.speed, cli.loop_replay);
+  } else {
+    spawn_simconnect_reader(state.clone(), config.clone());
+  }
+  adsb::spawn(state.clone());
+
+  let default_rate_ms = config.snapshot_rate_ms;
 
   loop {
     let (stream, _) = listener.accept().await?;
     let peer = stream.peer_addr().ok();
-    let mut rx = tx.subscribe();
+    let rx = tx.subscribe();
     tokio::spawn(async move {
       let ws_stream = match accept_async(stream).await {
         Ok(s) => s,
@@ -69,24 +141,24 @@ async fn main() -> anyhow::Result<()> {
           return;
         }
       };
-      let (mut ws_write, mut ws_read) = ws_stream.split();
+      let (ws_write, mut ws_read) = ws_stream.split();
 
       if let Some(peer) = peer {
         println!("Client connected: {peer}");
       }
 
-      let mut writer_task = tokio::spawn(async move {
-        while let Ok(payload) = rx.recv().await {
-          if ws_write.send(tokio_tungstenite::tungstenite::Message::Text(payload)).await.is_err() {
-            break;
-          }
-        }
-      });
+      let subscription = Arc::new(Mutex::new(Subscription::new(default_rate_ms)));
+      let (error_tx, error_rx) = mpsc::unbounded_channel::<String>();
+
+      let writer_task = spawn_connection_writer(ws_write, rx, subscription.clone(), error_rx);
 
       while let Some(Ok(msg)) = ws_read.next().await {
         if msg.is_close() {
           break;
         }
+        if let Message::Text(text) = &msg {
+          handle_client_command(text, &subscription, &error_tx);
+        }
       }
 
       writer_task.abort();
@@ -97,31 +169,68 @@ async fn main() -> anyhow::Result<()> {
   }
 }
 
-fn spawn_snapshot_broadcaster(state: Arc<Mutex<BridgeState>>, tx: broadcast::Sender<String>) {
+fn handle_client_command(text: &str, subscription: &Arc<Mutex<Subscription>>, error_tx: &mpsc::UnboundedSender<String>) {
+  match protocol::parse_command(text) {
+    Ok(protocol::ClientCommand::Subscribe { channels, rate_ms }) => {
+      subscription.lock().expect("subscription lock").apply(channels, rate_ms);
+    }
+    Err(err) => {
+      let _ = error_tx.send(protocol::error_reply(&err.to_string()));
+    }
+  }
+}
+
+fn spawn_connection_writer(
+  mut ws_write: futures_util::stream::SplitSink<tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>, Message>,
+  mut rx: broadcast::Receiver<BridgeEvent>,
+  subscription: Arc<Mutex<Subscription>>,
+  mut error_rx: mpsc::UnboundedReceiver<String>,
+) -> tokio::task::JoinHandle<()> {
+  tokio::spawn(async move {
+    loop {
+      tokio::select! {
+        event = rx.recv() => {
+          let Ok(event) = event else { break };
+          let should_send = subscription.lock().expect("subscription lock").should_send(&event);
+          if !should_send {
+            continue;
+          }
+          let Ok(payload) = event.to_json() else { continue };
+          if ws_write.send(Message::Text(payload)).await.is_err() {
+            break;
+          }
+        }
+        Some(reply) = error_rx.recv() => {
+          if ws_write.send(Message::Text(reply)).await.is_err() {
+            break;
+          }
+        }
+      }
+    }
+  })
+}
+
+fn spawn_snapshot_broadcaster(state: Arc<Mutex<BridgeState>>, tx: broadcast::Sender<BridgeEvent>, rate: Duration) {
   tokio::spawn(async move {
-    let mut ticker = interval(Duration::from_millis(200));
+    let mut ticker = interval(rate);
     loop {
       ticker.tick().await;
-      let snapshot = {
+      let (snapshot, traffic) = {
         let mut guard = state.lock().expect("state lock");
         guard.snapshot.time = unix_time_ms();
-        guard.snapshot.clone()
-      };
-      let msg = BridgeMessage {
-        r#type: "snapshot",
-        payload: &snapshot,
+        (guard.snapshot.clone(), guard.traffic.clone())
       };
-      if let Ok(payload) = serde_json::to_string(&msg) {
-        let _ = tx.send(payload);
-      }
+
+      let _ = tx.send(BridgeEvent::Snapshot(Box::new(snapshot)));
+      let _ = tx.send(BridgeEvent::Traffic(traffic));
     }
   });
 }
 
-fn spawn_simconnect_reader(_state: Arc<Mutex<BridgeState>>) {
+fn spawn_simconnect_reader(_state: Arc<Mutex<BridgeState>>, _config: config::Config) {
   #[cfg(windows)]
   {
-    simconnect_reader::spawn(_state);
+    simconnect_reader::spawn(_state, _config);
   }
 }
 
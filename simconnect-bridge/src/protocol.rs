@@ -0,0 +1,118 @@
+use crate::adsb::TrafficAircraft;
+use crate::BridgeSnapshot;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Channels a client can subscribe to; also the default set for a fresh
+/// connection that has not sent a `subscribe` command.
+pub const ALL_CHANNELS: &[&str] = &["snapshot", "traffic"];
+
+/// A structured broadcast value. Kept structured (rather than
+/// pre-serialized) so each connection can filter and throttle per channel
+/// before paying the cost of JSON-encoding it.
+#[derive(Clone, Debug)]
+pub enum BridgeEvent {
+  // Boxed: `BridgeSnapshot` is far larger than `Traffic`'s `Vec` pointer, and
+  // an unboxed variant would pad every `Traffic` event on the channel to
+  // the snapshot's size (clippy::large_enum_variant).
+  Snapshot(Box<BridgeSnapshot>),
+  Traffic(Vec<TrafficAircraft>),
+}
+
+impl BridgeEvent {
+  pub fn channel(&self) -> &'static str {
+    match self {
+      BridgeEvent::Snapshot(_) => "snapshot",
+      BridgeEvent::Traffic(_) => "traffic",
+    }
+  }
+
+  pub fn to_json(&self) -> serde_json::Result<String> {
+    match self {
+      BridgeEvent::Snapshot(payload) => {
+        serde_json::to_string(&Envelope { r#type: "snapshot", payload: payload.as_ref() })
+      }
+      BridgeEvent::Traffic(payload) => serde_json::to_string(&Envelope { r#type: "traffic", payload }),
+    }
+  }
+}
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+  r#type: &'a str,
+  payload: &'a T,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientCommand {
+  Subscribe {
+    channels: Vec<String>,
+    rate_ms: Option<u64>,
+  },
+}
+
+pub fn parse_command(text: &str) -> serde_json::Result<ClientCommand> {
+  serde_json::from_str(text)
+}
+
+#[derive(Serialize)]
+pub struct ErrorReply<'a> {
+  pub r#type: &'a str,
+  pub message: &'a str,
+}
+
+pub fn error_reply(message: &str) -> String {
+  let reply = ErrorReply { r#type: "error", message };
+  serde_json::to_string(&reply).unwrap_or_else(|_| r#"{"type":"error","message":"unknown command"}"#.to_string())
+}
+
+/// Per-connection view of which channels are wanted and how often, applied
+/// as a filter over the shared broadcast stream.
+#[derive(Debug)]
+pub struct Subscription {
+  channels: HashSet<String>,
+  rate: Duration,
+  last_sent: HashMap<String, Instant>,
+}
+
+impl Subscription {
+  /// A fresh connection that hasn't sent a `subscribe` command gets every
+  /// channel at `default_rate_ms` (the configured `snapshot_rate_ms`), so it
+  /// isn't silently throttled below whatever rate the operator configured.
+  pub fn new(default_rate_ms: u64) -> Self {
+    Self {
+      channels: ALL_CHANNELS.iter().map(|s| s.to_string()).collect(),
+      rate: Duration::from_millis(default_rate_ms.max(50)),
+      last_sent: HashMap::new(),
+    }
+  }
+
+  pub fn apply(&mut self, channels: Vec<String>, rate_ms: Option<u64>) {
+    self.channels = channels.into_iter().collect();
+    if let Some(rate_ms) = rate_ms {
+      self.rate = Duration::from_millis(rate_ms.max(50));
+    }
+    self.last_sent.clear();
+  }
+
+  /// Returns whether `event` is due to be sent to this connection right now,
+  /// recording the send if so.
+  pub fn should_send(&mut self, event: &BridgeEvent) -> bool {
+    let channel = event.channel();
+    if !self.channels.contains(channel) {
+      return false;
+    }
+    let now = Instant::now();
+    let due = self
+      .last_sent
+      .get(channel)
+      .map(|last| now.duration_since(*last) >= self.rate)
+      .unwrap_or(true);
+    if due {
+      self.last_sent.insert(channel.to_string(), now);
+    }
+    due
+  }
+}
@@ -0,0 +1,80 @@
+use super::{BridgeEvent, BridgeSnapshot, BridgeState};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Appends every broadcast snapshot to `path` as newline-delimited JSON, one
+/// line per tick, for later `--replay`.
+pub fn spawn_writer(path: String, mut rx: broadcast::Receiver<BridgeEvent>) {
+  tokio::spawn(async move {
+    let mut file = match OpenOptions::new().create(true).append(true).open(&path) {
+      Ok(file) => file,
+      Err(err) => {
+        eprintln!("Recorder failed to open {path}: {err}");
+        return;
+      }
+    };
+    println!("Recording snapshots to {path}");
+
+    loop {
+      match rx.recv().await {
+        Ok(BridgeEvent::Snapshot(snapshot)) => {
+          if let Ok(line) = serde_json::to_string(&snapshot) {
+            if writeln!(file, "{line}").is_err() {
+              break;
+            }
+          }
+        }
+        Ok(_) => {}
+        Err(broadcast::error::RecvError::Closed) => break,
+        Err(broadcast::error::RecvError::Lagged(_)) => {}
+      }
+    }
+  });
+}
+
+/// Feeds a recording back into `state` in place of a live SimConnect feed,
+/// honoring the original inter-frame timing (scaled by `speed`), optionally
+/// looping once the file is exhausted.
+pub fn spawn_replay(state: Arc<Mutex<BridgeState>>, path: String, speed: f64, loop_playback: bool) {
+  thread::spawn(move || loop {
+    if let Err(err) = run_replay(&state, &path, speed) {
+      eprintln!("Replay error: {err}");
+    }
+    if !loop_playback {
+      break;
+    }
+  });
+}
+
+fn run_replay(state: &Arc<Mutex<BridgeState>>, path: &str, speed: f64) -> anyhow::Result<()> {
+  println!("Replaying {path} at {speed}x");
+  let file = std::fs::File::open(path)?;
+  let reader = BufReader::new(file);
+  let mut prev_time: Option<u64> = None;
+
+  for line in reader.lines() {
+    let line = line?;
+    if line.trim().is_empty() {
+      continue;
+    }
+    let snapshot: BridgeSnapshot = serde_json::from_str(&line)?;
+
+    if let Some(prev) = prev_time {
+      let delta_ms = snapshot.time.saturating_sub(prev) as f64 / speed.max(0.01);
+      if delta_ms > 0.0 {
+        thread::sleep(Duration::from_millis(delta_ms as u64));
+      }
+    }
+    prev_time = Some(snapshot.time);
+
+    if let Ok(mut guard) = state.lock() {
+      guard.snapshot = snapshot;
+    }
+  }
+
+  Ok(())
+}
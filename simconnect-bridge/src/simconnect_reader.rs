@@ -1,6 +1,8 @@
 use super::BridgeState;
+use crate::config::Config;
 use chrono::Utc;
 use simconnect::{SimConnect, SimConnectRecv};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -8,25 +10,6 @@ use std::time::{Duration, Instant};
 const RETRY_DELAY: Duration = Duration::from_secs(2);
 const KG_PER_LB: f64 = 0.453_592_37;
 
-#[allow(clippy::too_many_arguments)]
-#[derive(Default, Clone, Copy)]
-struct DataFrame {
-  altitude_ft: f64,
-  ias_kt: f64,
-  gs_kt: f64,
-  mach: f64,
-  vs_fpm: f64,
-  on_ground: f64,
-  eng1_on: f64,
-  eng2_on: f64,
-  eng3_on: f64,
-  eng4_on: f64,
-  fuel_total_lb: f64,
-  weight_lb: f64,
-  flightplan_total_nm: f64,
-  flightplan_remaining_nm: f64,
-}
-
 #[derive(Default, Clone)]
 struct StateCache {
   last_on_ground: bool,
@@ -34,32 +17,21 @@ struct StateCache {
   fuel_start_kg: Option<f64>,
 }
 
-pub fn spawn(state: Arc<Mutex<BridgeState>>) {
+pub fn spawn(state: Arc<Mutex<BridgeState>>, config: Config) {
   thread::spawn(move || loop {
-    if let Err(err) = run_simconnect_loop(state.clone()) {
+    if let Err(err) = run_simconnect_loop(&state, &config) {
       eprintln!("SimConnect error: {err}");
       thread::sleep(RETRY_DELAY);
     }
   });
 }
 
-fn run_simconnect_loop(state: Arc<Mutex<BridgeState>>) -> anyhow::Result<()> {
+fn run_simconnect_loop(state: &Arc<Mutex<BridgeState>>, config: &Config) -> anyhow::Result<()> {
   let mut sim = SimConnect::new("Concorde EFB Bridge")?;
 
-  sim.add_to_data_definition(0, "PLANE ALTITUDE", "Feet")?;
-  sim.add_to_data_definition(0, "AIRSPEED INDICATED", "Knots")?;
-  sim.add_to_data_definition(0, "GROUND VELOCITY", "Knots")?;
-  sim.add_to_data_definition(0, "AIRSPEED MACH", "Mach")?;
-  sim.add_to_data_definition(0, "VERTICAL SPEED", "Feet per minute")?;
-  sim.add_to_data_definition(0, "SIM ON GROUND", "Bool")?;
-  sim.add_to_data_definition(0, "GENERAL ENG COMBUSTION:1", "Bool")?;
-  sim.add_to_data_definition(0, "GENERAL ENG COMBUSTION:2", "Bool")?;
-  sim.add_to_data_definition(0, "GENERAL ENG COMBUSTION:3", "Bool")?;
-  sim.add_to_data_definition(0, "GENERAL ENG COMBUSTION:4", "Bool")?;
-  sim.add_to_data_definition(0, "FUEL TOTAL QUANTITY WEIGHT", "Pounds")?;
-  sim.add_to_data_definition(0, "TOTAL WEIGHT", "Pounds")?;
-  sim.add_to_data_definition(0, "GPS FLIGHT PLAN TOTAL DISTANCE", "Nautical miles")?;
-  sim.add_to_data_definition(0, "GPS FLIGHT PLAN DISTANCE", "Nautical miles")?;
+  for var in &config.simvars {
+    sim.add_to_data_definition(0, &var.name, &var.unit)?;
+  }
 
   sim.request_data_on_sim_object(0, 0, 0, 0)?;
 
@@ -69,9 +41,9 @@ fn run_simconnect_loop(state: Arc<Mutex<BridgeState>>) -> anyhow::Result<()> {
   loop {
     match sim.get_next_dispatch()? {
       SimConnectRecv::SimObjectData(data) => {
-        let frame: DataFrame = data.into();
         last_dispatch = Instant::now();
-        update_snapshot(&state, &mut cache, frame);
+        let values = read_values(&data, config.simvars.len());
+        update_snapshot(state, &mut cache, config, &values);
       }
       _ => {
         if last_dispatch.elapsed() > Duration::from_secs(2) {
@@ -84,11 +56,35 @@ fn run_simconnect_loop(state: Arc<Mutex<BridgeState>>) -> anyhow::Result<()> {
   }
 }
 
-fn update_snapshot(state: &Arc<Mutex<BridgeState>>, cache: &mut StateCache, frame: DataFrame) {
-  let on_ground = frame.on_ground > 0.5;
-  let engines_on = frame.eng1_on > 0.5 || frame.eng2_on > 0.5 || frame.eng3_on > 0.5 || frame.eng4_on > 0.5;
-  let fuel_total_kg = frame.fuel_total_lb * KG_PER_LB;
-  let weight_kg = frame.weight_lb * KG_PER_LB;
+/// SAFETY: SimConnect packs one `f64` per variable added via
+/// `add_to_data_definition`, in declaration order, starting at `dwData`.
+/// This replaces the old fixed-`DataFrame` transmute with one sized to
+/// however many variables `config.simvars` declares.
+fn read_values(data: &simconnect::SIMCONNECT_RECV_SIMOBJECT_DATA, count: usize) -> Vec<f64> {
+  unsafe {
+    let ptr = std::ptr::addr_of!(data.dwData) as *const f64;
+    std::slice::from_raw_parts(ptr, count).to_vec()
+  }
+}
+
+fn update_snapshot(state: &Arc<Mutex<BridgeState>>, cache: &mut StateCache, config: &Config, values: &[f64]) {
+  let by_field: HashMap<&str, f64> = config
+    .simvars
+    .iter()
+    .zip(values)
+    .map(|(def, value)| (def.field.as_str(), *value))
+    .collect();
+  let field = |name: &str| by_field.get(name).copied().unwrap_or(0.0);
+
+  let on_ground = field("on_ground") > 0.5;
+  let engines_on = ["eng1_on", "eng2_on", "eng3_on", "eng4_on"]
+    .iter()
+    .any(|name| field(name) > 0.5);
+  let gs_kt = field("gs_kt");
+  let vs_fpm = field("vs_fpm");
+  let altitude_ft = field("altitude_ft");
+  let fuel_total_kg = field("fuel_total_lb") * KG_PER_LB;
+  let weight_kg = field("weight_lb") * KG_PER_LB;
 
   if engines_on && cache.fuel_start_kg.is_none() {
     cache.fuel_start_kg = Some(fuel_total_kg);
@@ -107,25 +103,25 @@ fn update_snapshot(state: &Arc<Mutex<BridgeState>>, cache: &mut StateCache, fram
 
   if !cache.last_on_ground && on_ground {
     if let Ok(mut guard) = state.lock() {
-      guard.snapshot.touchdown_fpm = Some(frame.vs_fpm);
+      guard.snapshot.touchdown_fpm = Some(vs_fpm);
     }
   }
 
-  if on_ground && frame.gs_kt > 35.0 && cache.takeoff_roll_time_utc.is_none() {
+  if on_ground && gs_kt > 35.0 && cache.takeoff_roll_time_utc.is_none() {
     cache.takeoff_roll_time_utc = Some(Utc::now().format("%H:%MZ").to_string());
   }
 
   let phase = if !engines_on {
     "Waiting"
-  } else if on_ground && frame.gs_kt < 5.0 {
+  } else if on_ground && gs_kt < 5.0 {
     "Engine Start"
-  } else if on_ground && frame.gs_kt < 35.0 {
+  } else if on_ground && gs_kt < 35.0 {
     "Taxiing"
   } else if on_ground {
     "Takeoff Roll"
-  } else if frame.altitude_ft < 10_000.0 && frame.vs_fpm > 500.0 {
+  } else if altitude_ft < 10_000.0 && vs_fpm > 500.0 {
     "Climb"
-  } else if frame.vs_fpm < -500.0 {
+  } else if vs_fpm < -500.0 {
     "Descent"
   } else {
     "Cruising"
@@ -133,17 +129,20 @@ fn update_snapshot(state: &Arc<Mutex<BridgeState>>, cache: &mut StateCache, fram
 
   cache.last_on_ground = on_ground;
 
+  // Internal helper variables (on_ground/engN_on/raw lb weights) feed the
+  // phase logic above but have no place on `BridgeSnapshot`, so they're
+  // skipped here; everything else is routed by field name.
+  let internal = ["on_ground", "eng1_on", "eng2_on", "eng3_on", "eng4_on", "fuel_total_lb", "weight_lb"];
+
   if let Ok(mut guard) = state.lock() {
-    guard.snapshot.altitude_ft = Some(frame.altitude_ft);
-    guard.snapshot.ias_kt = Some(frame.ias_kt);
-    guard.snapshot.gs_kt = Some(frame.gs_kt);
-    guard.snapshot.mach = Some(frame.mach);
-    guard.snapshot.vs_fpm = Some(frame.vs_fpm);
+    for (&name, &value) in &by_field {
+      if !internal.contains(&name) {
+        guard.snapshot.set_field(name, value);
+      }
+    }
     guard.snapshot.fuel_total_kg = Some(fuel_total_kg);
     guard.snapshot.fuel_burn_kg = fuel_burn_kg;
     guard.snapshot.weight_kg = Some(weight_kg);
-    guard.snapshot.flightplan_total_nm = Some(frame.flightplan_total_nm);
-    guard.snapshot.flightplan_remaining_nm = Some(frame.flightplan_remaining_nm);
     guard.snapshot.phase = Some(phase.to_string());
     guard.snapshot.takeoff_roll_time_utc = cache.takeoff_roll_time_utc.clone();
   }